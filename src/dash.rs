@@ -0,0 +1,99 @@
+//! A concurrent map whose values also contain their keys.
+//!
+//! Backed by [`dashmap::DashMap`], so `insert`, `remove`, `get`, and
+//! `get_cloned` take `&self` rather than `&mut self`. This lets an
+//! `AutoDashMap` be placed behind an `Arc` and mutated from multiple threads
+//! without an outer lock, unlike [`AutoHashMap`](crate::AutoHashMap) wrapped
+//! in a `RwLock`.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use dashmap::{DashMap, ReadOnlyView};
+
+use crate::AutoMapped;
+
+/// A concurrent map whose values also contain their keys, generic over the hasher
+#[derive(shrinkwraprs::Shrinkwrap, derive_more::From, derive_more::Into)]
+#[shrinkwrap(unsafe_ignore_visibility)]
+pub struct AutoDashMap<T: AutoMapped, S = RandomState>(DashMap<T::Key, T::Value, S>)
+where
+    T::Key: Eq + Hash;
+
+impl<T: AutoMapped> Default for AutoDashMap<T, RandomState>
+where
+    T::Key: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: AutoMapped> AutoDashMap<T, RandomState>
+where
+    T::Key: Eq + Hash,
+{
+    /// Constructor
+    pub fn new() -> Self {
+        Self(DashMap::new())
+    }
+
+    /// Constructor, pre-allocating capacity for at least `capacity` elements
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(DashMap::with_capacity(capacity))
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher + Clone> AutoDashMap<T, S>
+where
+    T::Key: Eq + Hash,
+{
+    /// Constructor, using a custom hasher
+    pub fn with_hasher(hasher: S) -> Self {
+        Self(DashMap::with_hasher(hasher))
+    }
+
+    /// Constructor, using a custom hasher and pre-allocating capacity for at
+    /// least `capacity` elements
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self(DashMap::with_capacity_and_hasher(capacity, hasher))
+    }
+
+    /// Split `t` into its key and value, storing the pair
+    pub fn insert(&self, t: T) -> Option<T::Value> {
+        let (k, v) = t.split();
+        self.0.insert(k, v)
+    }
+
+    /// Like `get`, but joins the key and value back into a `T`.
+    /// Requires cloning both key and value
+    pub fn get_cloned<Q>(&self, k: &Q) -> Option<T>
+    where
+        T::Key: Borrow<Q> + Clone,
+        T::Value: Clone,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0
+            .get(k)
+            .map(|entry| T::join((entry.key().clone(), entry.value().clone())))
+    }
+
+    /// Like `remove`, but joins the key and value back into a `T`
+    pub fn remove_cloned<Q>(&self, k: &Q) -> Option<T>
+    where
+        T::Key: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.remove(k).map(T::join)
+    }
+
+    /// Consume the map into a lock-free, read-only view. Useful once a map
+    /// has finished being populated and only needs to be read from.
+    pub fn into_read_only(self) -> ReadOnlyView<T::Key, T::Value, S> {
+        self.0.into_read_only()
+    }
+}
+
+// `get` and `remove` by raw key, as well as all other `DashMap` methods, are
+// available directly through the `Shrinkwrap`-derived `Deref`.
@@ -0,0 +1,108 @@
+//! A persistent, clone-cheap map whose values also contain their keys.
+//!
+//! Backed by [`im::HashMap`], a hash array mapped trie (HAMT) with
+//! structural sharing: `insert` and `remove` return a new map sharing most
+//! of its structure with the old one in O(log n), and `Clone` is O(1) via
+//! reference counting. Useful for undo stacks, speculative edits, or
+//! sharing a keyed index across async tasks without deep copies.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use im::HashMap;
+
+use crate::AutoMapped;
+
+/// A persistent map whose values also contain their keys, generic over the hasher
+#[derive(shrinkwraprs::Shrinkwrap, derive_more::From, derive_more::Into)]
+#[shrinkwrap(unsafe_ignore_visibility)]
+pub struct AutoImMap<T: AutoMapped, S = RandomState>(HashMap<T::Key, T::Value, S>)
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone;
+
+impl<T: AutoMapped, S: BuildHasher> std::fmt::Debug for AutoImMap<T, S>
+where
+    T::Key: Hash + Eq + Clone + std::fmt::Debug,
+    T::Value: Clone + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: AutoMapped, S: Clone> Clone for AutoImMap<T, S>
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher> PartialEq for AutoImMap<T, S>
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher> Eq for AutoImMap<T, S>
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone + Eq,
+{
+}
+
+impl<T: AutoMapped, S: Default + BuildHasher> Default for AutoImMap<T, S>
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone,
+{
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
+impl<T: AutoMapped> AutoImMap<T, RandomState>
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone,
+{
+    /// Constructor
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher + Clone> AutoImMap<T, S>
+where
+    T::Key: Hash + Eq + Clone,
+    T::Value: Clone,
+{
+    /// Constructor, using a custom hasher
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self(HashMap::with_hasher(hash_builder))
+    }
+
+    /// Split `t` into its key and value, returning a new map containing the
+    /// pair, sharing structure with `self`
+    pub fn insert(&self, t: T) -> Self {
+        let (k, v) = t.split();
+        Self(self.0.update(k, v))
+    }
+
+    /// Returns a new map without the given key, sharing structure with `self`
+    pub fn remove<Q>(&self, k: &Q) -> Self
+    where
+        T::Key: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Self(self.0.without(k))
+    }
+}
@@ -1,26 +1,31 @@
 //! A simple pattern to implement maps where the value type also contains the key type.
-//! Implementations for `HashMap` and `BTreeMap` from `std::collections` are provided.
+//! `AutoBTreeMap` is backed by `std::collections::BTreeMap`; `AutoHashMap` is backed by
+//! `hashbrown::HashMap` so that it can support allocation-free lookups by composite keys
+//! (see [`Lookup`]).
 //!
 //! ```
-//! use std::collections::HashMap;
 //! use automap::{AutoHashMap, AutoMapped};
 //!
 //! // Let's say we want a `Person` to be keyed by their `name` in a HashMap
-//! #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+//! #[derive(Debug, Clone, PartialEq, Eq)]
 //! struct Person {
 //!     name: String,
 //!     age: u16,
 //! }
 //!
-//! // We can specify how to derive the key from the value
+//! // We can specify how to split the value into its key and the rest.
 //! // As long as the Key type meets the bounds for a normal HashMap key, we
-//! // can use this value in an AutoHashMap.
-//! // (Similarly for BTreeMap.)
+//! // can use this value in an AutoHashMap. (Similarly for BTreeMap.)
 //! impl AutoMapped for Person {
 //!     type Key = String;
+//!     type Value = u16;
 //!
-//!     fn key(&self) -> &Self::Key {
-//!         &self.name
+//!     fn split(self) -> (Self::Key, Self::Value) {
+//!         (self.name, self.age)
+//!     }
+//!
+//!     fn join((name, age): (Self::Key, Self::Value)) -> Self {
+//!         Self { name, age }
 //!     }
 //! }
 //!
@@ -29,13 +34,19 @@
 //! let michelle = Person { name: "Michelle".into(), age: 37 };
 //! map.insert(michelle.clone());
 //!
-//! // You can access all other normal HashMap methods directly:
-//! assert_eq!(map.get("Michelle".into()), Some(&michelle));
-//! assert_eq!(map.remove("Michelle".into()), Some(michelle));
+//! // You can access all other normal HashMap methods directly, through Deref:
+//! assert_eq!(map.get("Michelle"), Some(&37));
+//! assert_eq!(map.remove("Michelle"), Some(37));
 //!
-//! // We can also go From and Into a normal HashMap easily.
-//! let inner: HashMap<_, _> = map.into();
-//! let map: AutoHashMap<_> = inner.into();
+//! // We can also go From and Into a plain `hashbrown::HashMap` easily, as
+//! // long as both sides agree on the hasher (`AutoHashMap` defaults to the
+//! // same `RandomState` as `std::collections::HashMap`, not hashbrown's own
+//! // default hasher).
+//! map.insert(michelle.clone());
+//! let inner: hashbrown::HashMap<String, u16, std::collections::hash_map::RandomState> =
+//!     map.into();
+//! let map: AutoHashMap<Person> = inner.into();
+//! assert_eq!(map.get_cloned("Michelle".to_string()), Some(michelle));
 //! ```
 
 #![deny(missing_docs)]
@@ -43,7 +54,22 @@
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "dashmap")]
+mod dash;
+#[cfg(feature = "dashmap")]
+pub use dash::AutoDashMap;
+
+#[cfg(feature = "im")]
+mod imm;
+#[cfg(feature = "im")]
+pub use imm::AutoImMap;
+
+mod multi;
+pub use multi::{AutoIndexed, AutoIndexed2, AutoMultiMap, AutoMultiMap2};
+
 use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
 
 /// Trait that describes how to extract a key out of a value
 pub trait AutoMapped {
@@ -154,10 +180,262 @@ macro_rules! implementation {
     };
 }
 
-// Implementations for both HashMap and BTreeMap are very similar
-implementation!(AutoHashMap, HashMap, AutoHashMapKey, AutoHashMapValue);
+// BTreeMap has no notion of a hasher, so it can use the generic macro as-is.
 implementation!(AutoBTreeMap, BTreeMap, AutoBTreeMapKey, AutoBTreeMapValue);
 
+use hashbrown::HashMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A trait for probing a map by an equivalence relation without needing to
+/// construct an owned key. This is useful for composite keys like
+/// `(String, String)`, where there is no borrowed form that can be
+/// constructed without allocating.
+///
+/// `Q: Lookup<K>` must hash identically to any `K` it considers `equal`;
+/// [`AutoHashMap::get_with`] and [`AutoHashMap::remove_with`] debug-assert
+/// this invariant when a match is found.
+pub trait Lookup<K>: Hash {
+    /// Returns whether `self` is equivalent to `key`
+    fn equal(&self, key: &K) -> bool;
+}
+
+impl<Q, K> Lookup<K> for Q
+where
+    Q: Hash + Eq + ?Sized,
+    K: Borrow<Q>,
+{
+    fn equal(&self, key: &K) -> bool {
+        key.borrow() == self
+    }
+}
+
+/// A map whose values also contain their keys, generic over the hasher
+///
+/// Defaults to the same `RandomState` hasher as `std::collections::HashMap`,
+/// but a different `S: BuildHasher` can be plugged in (e.g. from `ahash` or
+/// `fnv`) for hot-path key types, or for deterministic iteration order.
+/// Backed by `hashbrown::HashMap` rather than `std::collections::HashMap` so
+/// that [`get_with`](AutoHashMap::get_with)/[`remove_with`](AutoHashMap::remove_with)
+/// can probe the table via `raw_entry` using any [`Lookup<T::Key>`](Lookup).
+#[derive(shrinkwraprs::Shrinkwrap, derive_more::From, derive_more::Into)]
+#[shrinkwrap(mutable, unsafe_ignore_visibility)]
+pub struct AutoHashMap<T: AutoMapped, S = RandomState>(HashMap<T::Key, T::Value, S>)
+where
+    T::Key: AutoHashMapKey,
+    T::Value: AutoHashMapValue;
+
+// Manual impls below (rather than `#[derive(..)]`) because the derive macros
+// bound every generic param, while `HashMap`'s own impls bound `S` only as each
+// trait needs (e.g. `S: BuildHasher` for `PartialEq`, `S: Clone` for `Clone`).
+
+impl<T: AutoMapped, S> std::fmt::Debug for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey + std::fmt::Debug,
+    T::Value: AutoHashMapValue + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: AutoMapped, S: Clone> Clone for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey + Clone,
+    T::Value: AutoHashMapValue + Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher> PartialEq for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey,
+    T::Value: AutoHashMapValue + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher> Eq for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey,
+    T::Value: AutoHashMapValue + Eq,
+{
+}
+
+impl<T: AutoMapped, S: Default> Default for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey,
+    T::Value: AutoHashMapValue,
+{
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
+// Likewise hand-written rather than `#[cfg_attr(feature = "serde", derive(..))]`:
+// `hashbrown::HashMap`'s own `Serialize` impl only bounds `S: BuildHasher`, and
+// its `Deserialize` impl needs `S: BuildHasher + Default`, so a blanket derive
+// on `AutoHashMap<T, S>` (which has no bound on `S` at all) can't typecheck.
+// A consuming `Cargo.toml` also needs to forward this feature to hashbrown
+// itself, e.g. `serde = ["dep:serde", "hashbrown/serde"]`.
+#[cfg(feature = "serde")]
+impl<T: AutoMapped, S: BuildHasher> Serialize for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey,
+    T::Value: AutoHashMapValue,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: AutoMapped, S: BuildHasher + Default> Deserialize<'de> for AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey,
+    T::Value: AutoHashMapValue,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        HashMap::<T::Key, T::Value, S>::deserialize(deserializer).map(Self)
+    }
+}
+
+impl<T: AutoMapped> AutoHashMap<T, RandomState>
+where
+    T::Key: AutoHashMapKey + Clone,
+    T::Value: AutoHashMapValue,
+{
+    /// Constructor
+    pub fn new() -> Self {
+        Self(HashMap::default())
+    }
+
+    /// Constructor, pre-allocating capacity for at least `capacity` elements
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(HashMap::with_capacity_and_hasher(
+            capacity,
+            RandomState::default(),
+        ))
+    }
+}
+
+impl<T: AutoMapped, S: BuildHasher> AutoHashMap<T, S>
+where
+    T::Key: AutoHashMapKey + Clone,
+    T::Value: AutoHashMapValue,
+{
+    /// Constructor, using a custom hasher
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self(HashMap::with_capacity_and_hasher(0, hash_builder))
+    }
+
+    /// Constructor, using a custom hasher and pre-allocating capacity for at
+    /// least `capacity` elements
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self(HashMap::with_capacity_and_hasher(capacity, hash_builder))
+    }
+
+    /// Split `t` into its key and value, storing the pair and returning the
+    /// value previously stored under that key, if any
+    pub fn insert(&mut self, t: T) -> Option<T::Value>
+    where
+        T::Value: Clone,
+    {
+        let (k, v) = t.split();
+        self.0.insert(k, v)
+    }
+
+    /// Like `insert`, but returns a T, which requires cloning the key
+    pub fn insert_cloned(&mut self, t: T) -> Option<T>
+    where
+        T::Value: Clone,
+    {
+        let (k, v) = t.split();
+        self.0
+            .insert(k.clone(), v)
+            .map(|val| T::join((k, val.to_owned())))
+    }
+
+    /// Like `remove`, but returns a T, which requires cloning the key
+    pub fn remove_cloned<'a, C>(&mut self, k: C) -> Option<T>
+    where
+        T::Value: Clone,
+        C: Clone + Borrow<T::Key> + AutoHashMapKey,
+        T::Key: Borrow<C>,
+    {
+        self.0
+            .remove(&k)
+            .map(|val| T::join((k.borrow().to_owned(), val.to_owned())))
+    }
+
+    /// Get an owned copy of the full type associated with this key.
+    /// Requires cloning both key and value
+    pub fn get_cloned<'a, C>(&self, k: C) -> Option<T>
+    where
+        T::Value: Clone,
+        C: Clone + Borrow<T::Key> + AutoHashMapKey,
+        T::Key: Borrow<C>,
+    {
+        self.0
+            .get(&k)
+            .map(|val| T::join((k.borrow().to_owned(), val.to_owned())))
+    }
+
+    /// Pass-through for inner `into_iter`
+    pub fn into_iter(self) -> impl Iterator<Item = (T::Key, T::Value)> {
+        self.0.into_iter()
+    }
+
+    /// Get the full `T` for any probe key equivalent to a stored key,
+    /// without needing to construct an owned `T::Key`. Requires cloning
+    /// both key and value.
+    pub fn get_with<Q>(&self, probe: &Q) -> Option<T>
+    where
+        T::Key: Clone,
+        T::Value: Clone,
+        Q: Lookup<T::Key> + ?Sized,
+    {
+        let hash = self.0.hasher().hash_one(probe);
+        let found = self.0.raw_entry().from_hash(hash, |k| probe.equal(k));
+        debug_assert!(
+            found.is_none_or(|(k, _)| self.0.hasher().hash_one(k) == hash),
+            "Lookup::equal matched a key whose Hash disagrees with the probe's Hash"
+        );
+        found.map(|(k, v)| T::join((k.clone(), v.clone())))
+    }
+
+    /// Like `get_with`, but removes the matched entry
+    pub fn remove_with<Q>(&mut self, probe: &Q) -> Option<T>
+    where
+        Q: Lookup<T::Key> + ?Sized,
+    {
+        use hashbrown::hash_map::RawEntryMut;
+
+        let hash = self.0.hasher().hash_one(probe);
+        let entry = match self.0.raw_entry_mut().from_hash(hash, |k| probe.equal(k)) {
+            RawEntryMut::Occupied(entry) => entry,
+            RawEntryMut::Vacant(_) => return None,
+        };
+        let (k, v) = entry.remove_entry();
+        debug_assert_eq!(
+            self.0.hasher().hash_one(&k),
+            hash,
+            "Lookup::equal matched a key whose Hash disagrees with the probe's Hash"
+        );
+        Some(T::join((k, v)))
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "serde")] {
         /// The constraints on an AutoHashMap key
@@ -186,11 +464,11 @@ cfg_if::cfg_if! {
 
         /// The constraints on an AutoHashMap Value
         pub trait AutoHashMapValue {}
-        impl<T> AutoHashMapValue for T where T {}
+        impl<T> AutoHashMapValue for T {}
 
         /// The constraints on an AutoBTreeMap Value
         pub trait AutoBTreeMapValue {}
-        impl<T> AutoBTreeMapValue for T where T {}
+        impl<T> AutoBTreeMapValue for T {}
 
     }
 }
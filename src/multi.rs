@@ -0,0 +1,294 @@
+//! A small in-memory multi-index table: values keyed primarily by one key,
+//! with one or two secondary indices for lookups by other derived keys.
+//!
+//! The primary `HashMap<T::Key, T::Value>` is the source of truth; one
+//! auxiliary `HashMap<SecondaryKey, T::Key>` per secondary index is kept in
+//! sync on every `insert`/`remove`. A secondary entry is only ever purged
+//! when it still points at the value being replaced or removed, so when two
+//! values share a derived secondary key (e.g. two people of the same age),
+//! replacing or removing one never purges the other's index entry. Each
+//! secondary key maps to a single primary key: the most recently inserted
+//! value with that secondary key. An earlier value whose entry was
+//! overwritten this way is not re-indexed.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::AutoMapped;
+
+/// Extends [`AutoMapped`] with a secondary key derived from the value, so a
+/// single collection can support lookups by more than one key over the same
+/// stored values (in addition to the primary key).
+pub trait AutoIndexed: AutoMapped {
+    /// The secondary key type
+    type SecondaryKey;
+
+    /// Derive the secondary key from a value
+    fn secondary_key(value: &Self::Value) -> Self::SecondaryKey;
+}
+
+/// Extends [`AutoIndexed`] with a second, independent secondary key, so a
+/// collection can support lookups by two different derived keys at once
+/// (e.g. `by_name` and `by_id` over the same values).
+pub trait AutoIndexed2: AutoIndexed {
+    /// The second secondary key type
+    type SecondaryKey2;
+
+    /// Derive the second secondary key from a value
+    fn secondary_key2(value: &Self::Value) -> Self::SecondaryKey2;
+}
+
+/// A map whose values also contain their primary key, with a secondary
+/// index for lookup by another derived key.
+///
+/// ```
+/// use automap::{AutoIndexed, AutoMapped, AutoMultiMap};
+///
+/// #[derive(Debug, Clone, PartialEq, Eq)]
+/// struct Person {
+///     name: String,
+///     age: u16,
+/// }
+///
+/// impl AutoMapped for Person {
+///     type Key = String;
+///     type Value = u16;
+///
+///     fn split(self) -> (Self::Key, Self::Value) {
+///         (self.name, self.age)
+///     }
+///
+///     fn join((name, age): (Self::Key, Self::Value)) -> Self {
+///         Self { name, age }
+///     }
+/// }
+///
+/// impl AutoIndexed for Person {
+///     type SecondaryKey = u16;
+///
+///     fn secondary_key(age: &Self::Value) -> Self::SecondaryKey {
+///         *age
+///     }
+/// }
+///
+/// let mut table = AutoMultiMap::<Person>::new();
+/// table.insert(Person { name: "Bob".into(), age: 23 });
+///
+/// assert_eq!(table.get("Bob"), Some(&23));
+/// assert_eq!(table.get_by_secondary(&23), Some(&23));
+/// ```
+pub struct AutoMultiMap<T: AutoIndexed>
+where
+    T::Key: Clone + Hash + Eq,
+    T::SecondaryKey: Clone + Hash + Eq,
+{
+    primary: HashMap<T::Key, T::Value>,
+    secondary: HashMap<T::SecondaryKey, T::Key>,
+}
+
+impl<T: AutoIndexed> Default for AutoMultiMap<T>
+where
+    T::Key: Clone + Hash + Eq,
+    T::SecondaryKey: Clone + Hash + Eq,
+{
+    fn default() -> Self {
+        Self {
+            primary: HashMap::default(),
+            secondary: HashMap::default(),
+        }
+    }
+}
+
+impl<T: AutoIndexed> AutoMultiMap<T>
+where
+    T::Key: Clone + Hash + Eq,
+    T::SecondaryKey: Clone + Hash + Eq,
+{
+    /// Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split `t` into its primary key and value, indexing the value under
+    /// its primary key and its secondary key. If the primary key was
+    /// already present, its stale secondary entry is purged if it still
+    /// points at the replaced value.
+    pub fn insert(&mut self, t: T) -> Option<T::Value> {
+        let (k, v) = t.split();
+        let old = self.primary.insert(k.clone(), v);
+        if let Some(old) = &old {
+            let old_sk = T::secondary_key(old);
+            if self.secondary.get(&old_sk) == Some(&k) {
+                self.secondary.remove(&old_sk);
+            }
+        }
+        let new_sk = T::secondary_key(self.primary.get(&k).expect("just inserted"));
+        self.secondary.insert(new_sk, k);
+        old
+    }
+
+    /// Remove by primary key, purging the secondary entry if it still
+    /// points at the removed value
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<T::Value>
+    where
+        T::Key: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let v = self.primary.remove(k)?;
+        let sk = T::secondary_key(&v);
+        if self.secondary.get(&sk).map(|pk| pk.borrow()) == Some(k) {
+            self.secondary.remove(&sk);
+        }
+        Some(v)
+    }
+
+    /// Get a value by its primary key
+    pub fn get<Q>(&self, k: &Q) -> Option<&T::Value>
+    where
+        T::Key: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.primary.get(k)
+    }
+
+    /// Get a value by its secondary key
+    pub fn get_by_secondary<Q>(&self, sk: &Q) -> Option<&T::Value>
+    where
+        T::SecondaryKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.secondary.get(sk).and_then(|k| self.primary.get(k))
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.primary.len()
+    }
+
+    /// Whether the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_empty()
+    }
+}
+
+/// Like [`AutoMultiMap`], but with two independent secondary indices
+/// (see [`AutoIndexed2`]).
+pub struct AutoMultiMap2<T: AutoIndexed2>
+where
+    T::Key: Clone + Hash + Eq,
+    T::SecondaryKey: Clone + Hash + Eq,
+    T::SecondaryKey2: Clone + Hash + Eq,
+{
+    primary: HashMap<T::Key, T::Value>,
+    secondary: HashMap<T::SecondaryKey, T::Key>,
+    secondary2: HashMap<T::SecondaryKey2, T::Key>,
+}
+
+impl<T: AutoIndexed2> Default for AutoMultiMap2<T>
+where
+    T::Key: Clone + Hash + Eq,
+    T::SecondaryKey: Clone + Hash + Eq,
+    T::SecondaryKey2: Clone + Hash + Eq,
+{
+    fn default() -> Self {
+        Self {
+            primary: HashMap::default(),
+            secondary: HashMap::default(),
+            secondary2: HashMap::default(),
+        }
+    }
+}
+
+impl<T: AutoIndexed2> AutoMultiMap2<T>
+where
+    T::Key: Clone + Hash + Eq,
+    T::SecondaryKey: Clone + Hash + Eq,
+    T::SecondaryKey2: Clone + Hash + Eq,
+{
+    /// Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split `t` into its primary key and value, indexing the value under
+    /// its primary key and both secondary keys. If the primary key was
+    /// already present, its stale secondary entries are purged wherever
+    /// they still point at the replaced value.
+    pub fn insert(&mut self, t: T) -> Option<T::Value> {
+        let (k, v) = t.split();
+        let old = self.primary.insert(k.clone(), v);
+        if let Some(old) = &old {
+            let old_sk = T::secondary_key(old);
+            if self.secondary.get(&old_sk) == Some(&k) {
+                self.secondary.remove(&old_sk);
+            }
+            let old_sk2 = T::secondary_key2(old);
+            if self.secondary2.get(&old_sk2) == Some(&k) {
+                self.secondary2.remove(&old_sk2);
+            }
+        }
+        let v_ref = self.primary.get(&k).expect("just inserted");
+        let new_sk = T::secondary_key(v_ref);
+        let new_sk2 = T::secondary_key2(v_ref);
+        self.secondary.insert(new_sk, k.clone());
+        self.secondary2.insert(new_sk2, k);
+        old
+    }
+
+    /// Remove by primary key, purging every secondary entry that still
+    /// points at the removed value
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<T::Value>
+    where
+        T::Key: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let v = self.primary.remove(k)?;
+        let sk = T::secondary_key(&v);
+        if self.secondary.get(&sk).map(|pk| pk.borrow()) == Some(k) {
+            self.secondary.remove(&sk);
+        }
+        let sk2 = T::secondary_key2(&v);
+        if self.secondary2.get(&sk2).map(|pk| pk.borrow()) == Some(k) {
+            self.secondary2.remove(&sk2);
+        }
+        Some(v)
+    }
+
+    /// Get a value by its primary key
+    pub fn get<Q>(&self, k: &Q) -> Option<&T::Value>
+    where
+        T::Key: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.primary.get(k)
+    }
+
+    /// Get a value by its first secondary key
+    pub fn get_by_secondary<Q>(&self, sk: &Q) -> Option<&T::Value>
+    where
+        T::SecondaryKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.secondary.get(sk).and_then(|k| self.primary.get(k))
+    }
+
+    /// Get a value by its second secondary key
+    pub fn get_by_secondary2<Q>(&self, sk: &Q) -> Option<&T::Value>
+    where
+        T::SecondaryKey2: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.secondary2.get(sk).and_then(|k| self.primary.get(k))
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.primary.len()
+    }
+
+    /// Whether the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_empty()
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use super::*;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Person {
     name: String,
     age: u16,
@@ -57,6 +58,80 @@ fn auto_hashmap() {
     assert_eq!(hashmap.remove("Ruth".into()), Some(ruth.age));
 }
 
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Pet {
+    owner: String,
+    name: String,
+    age: u16,
+}
+
+impl AutoMapped for Pet {
+    type Key = (String, String);
+    type Value = u16;
+
+    fn split(self) -> (Self::Key, Self::Value) {
+        ((self.owner, self.name), self.age)
+    }
+
+    fn join(((owner, name), age): (Self::Key, Self::Value)) -> Self {
+        Self { owner, name, age }
+    }
+}
+
+/// Probes a `(String, String)`-keyed map by `(&str, &str)` without
+/// allocating an owned `(String, String)` just to do the lookup.
+struct PetKey<'a>(&'a str, &'a str);
+
+impl Hash for PetKey<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+        self.1.hash(state);
+    }
+}
+
+impl Lookup<(String, String)> for PetKey<'_> {
+    fn equal(&self, key: &(String, String)) -> bool {
+        self.0 == key.0 && self.1 == key.1
+    }
+}
+
+#[test]
+fn auto_hashmap_get_with_composite_key() {
+    let mut hashmap = AutoHashMap::<Pet>::new();
+    let rex = Pet {
+        owner: "Alice".into(),
+        name: "Rex".into(),
+        age: 4,
+    };
+    assert_eq!(hashmap.insert(rex.clone()), None);
+    assert_eq!(
+        hashmap.get_with(&PetKey("Alice", "Rex")),
+        Some(rex.clone())
+    );
+    assert_eq!(hashmap.get_with(&PetKey("Alice", "Fido")), None);
+    assert_eq!(
+        hashmap.remove_with(&PetKey("Alice", "Rex")),
+        Some(rex.clone())
+    );
+    assert_eq!(hashmap.get_with(&PetKey("Alice", "Rex")), None);
+}
+
+#[test]
+fn auto_hashmap_with_custom_hasher() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    let mut hashmap =
+        AutoHashMap::<Person, BuildHasherDefault<DefaultHasher>>::with_hasher(Default::default());
+    let bob = Person {
+        name: "Bob".into(),
+        age: 23,
+    };
+    assert_eq!(hashmap.insert(bob.clone()), None);
+    assert_eq!(hashmap.get_cloned("Bob".to_string()), Some(bob.clone()));
+    assert_eq!(hashmap.remove("Bob".into()), Some(bob.age));
+}
+
 #[test]
 fn auto_btreemap() {
     let mut hashmap = AutoBTreeMap::<Person>::new();
@@ -84,3 +159,207 @@ fn auto_btreemap() {
     assert_eq!(hashmap.remove("Bob"), Some(bob2.age));
     assert_eq!(hashmap.remove("Ruth"), Some(ruth.age));
 }
+
+#[cfg(feature = "dashmap")]
+#[test]
+fn auto_dashmap() {
+    let dashmap = AutoDashMap::<Person>::new();
+    let bob1 = Person {
+        name: "Bob".into(),
+        age: 23,
+    };
+    let bob2 = Person {
+        name: "Bob".into(),
+        age: 41,
+    };
+    let ruth = Person {
+        name: "Ruth".into(),
+        age: 32,
+    };
+    assert_eq!(dashmap.insert(bob1.clone()), None);
+    assert_eq!(dashmap.insert(ruth.clone()), None);
+    assert_eq!(dashmap.insert(bob2.clone()), Some(bob1.age));
+    assert_eq!(dashmap.len(), 2);
+    assert_eq!(dashmap.get_cloned("Bob"), Some(bob2.clone()));
+    assert_eq!(dashmap.remove_cloned("Bob"), Some(bob2));
+    assert_eq!(dashmap.remove_cloned("Ruth"), Some(ruth));
+}
+
+// Guards against a regression to the `Serialize`/`Deserialize` bounds on
+// `AutoHashMap` (no data-format crate is a dependency of this crate, so this
+// only asserts that the impls typecheck, rather than round-tripping).
+#[cfg(feature = "serde")]
+#[test]
+fn auto_hashmap_implements_serde() {
+    fn assert_impls<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+    assert_impls::<AutoHashMap<Person>>();
+}
+
+#[cfg(feature = "im")]
+#[test]
+fn auto_immap() {
+    let empty = AutoImMap::<Person>::new();
+    let bob = Person {
+        name: "Bob".into(),
+        age: 23,
+    };
+    let ruth = Person {
+        name: "Ruth".into(),
+        age: 32,
+    };
+
+    let with_bob = empty.insert(bob.clone());
+    let with_both = with_bob.insert(ruth.clone());
+
+    // Earlier snapshots are untouched by later inserts
+    assert_eq!(empty.len(), 0);
+    assert_eq!(with_bob.len(), 1);
+    assert_eq!(with_both.len(), 2);
+    assert_eq!(with_both.get("Bob"), Some(&bob.age));
+
+    let without_bob = with_both.remove("Bob");
+    assert_eq!(without_bob.len(), 1);
+    assert_eq!(without_bob.get("Bob"), None);
+    assert_eq!(with_both.get("Bob"), Some(&bob.age));
+}
+
+impl AutoIndexed for Person {
+    type SecondaryKey = u16;
+
+    fn secondary_key(age: &Self::Value) -> Self::SecondaryKey {
+        *age
+    }
+}
+
+#[test]
+fn auto_multimap() {
+    let mut table = AutoMultiMap::<Person>::new();
+    let bob = Person {
+        name: "Bob".into(),
+        age: 23,
+    };
+    let ruth = Person {
+        name: "Ruth".into(),
+        age: 32,
+    };
+    assert_eq!(table.insert(bob.clone()), None);
+    assert_eq!(table.insert(ruth.clone()), None);
+    assert_eq!(table.len(), 2);
+
+    assert_eq!(table.get("Bob"), Some(&bob.age));
+    assert_eq!(table.get_by_secondary(&23), Some(&bob.age));
+    assert_eq!(table.get_by_secondary(&32), Some(&ruth.age));
+
+    // Re-inserting under the same primary key with a new secondary key
+    // purges the stale secondary entry
+    let older_bob = Person {
+        name: "Bob".into(),
+        age: 24,
+    };
+    assert_eq!(table.insert(older_bob.clone()), Some(bob.age));
+    assert_eq!(table.get_by_secondary(&23), None);
+    assert_eq!(table.get_by_secondary(&24), Some(&older_bob.age));
+
+    assert_eq!(table.remove("Bob"), Some(older_bob.age));
+    assert_eq!(table.get_by_secondary(&24), None);
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn auto_multimap_remove_does_not_clobber_colliding_secondary_key() {
+    // Bob and Ruth share a secondary key (age 23). Removing Bob must not
+    // delete Ruth's still-live secondary entry.
+    let mut table = AutoMultiMap::<Person>::new();
+    let bob = Person {
+        name: "Bob".into(),
+        age: 23,
+    };
+    let ruth = Person {
+        name: "Ruth".into(),
+        age: 23,
+    };
+    assert_eq!(table.insert(bob.clone()), None);
+    assert_eq!(table.insert(ruth.clone()), None);
+
+    assert_eq!(table.remove("Bob"), Some(bob.age));
+    assert_eq!(table.get("Bob"), None);
+    assert_eq!(table.get("Ruth"), Some(&ruth.age));
+    assert_eq!(table.get_by_secondary(&23), Some(&ruth.age));
+}
+
+/// A `Pet`-like value keyed by its own label and indexed by both its owner's
+/// name and its use count, to exercise `AutoMultiMap2`'s two independent
+/// secondary indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Toy {
+    owner: String,
+    label: String,
+    uses: u32,
+}
+
+impl AutoMapped for Toy {
+    type Key = String;
+    type Value = (String, u32);
+
+    fn split(self) -> (Self::Key, Self::Value) {
+        (self.label, (self.owner, self.uses))
+    }
+
+    fn join((label, (owner, uses)): (Self::Key, Self::Value)) -> Self {
+        Self {
+            owner,
+            label,
+            uses,
+        }
+    }
+}
+
+impl AutoIndexed for Toy {
+    type SecondaryKey = String;
+
+    fn secondary_key(value: &Self::Value) -> Self::SecondaryKey {
+        value.0.clone()
+    }
+}
+
+impl AutoIndexed2 for Toy {
+    type SecondaryKey2 = u32;
+
+    fn secondary_key2(value: &Self::Value) -> Self::SecondaryKey2 {
+        value.1
+    }
+}
+
+#[test]
+fn auto_multimap2() {
+    let mut table = AutoMultiMap2::<Toy>::new();
+    let ball = Toy {
+        owner: "Bob".into(),
+        label: "Ball".into(),
+        uses: 3,
+    };
+    let bone = Toy {
+        owner: "Ruth".into(),
+        label: "Bone".into(),
+        uses: 7,
+    };
+    assert_eq!(table.insert(ball.clone()), None);
+    assert_eq!(table.insert(bone.clone()), None);
+
+    assert_eq!(table.get("Ball"), Some(&(ball.owner.clone(), ball.uses)));
+    assert_eq!(
+        table.get_by_secondary("Bob"),
+        Some(&(ball.owner.clone(), ball.uses))
+    );
+    assert_eq!(
+        table.get_by_secondary2(&7),
+        Some(&(bone.owner.clone(), bone.uses))
+    );
+
+    assert_eq!(
+        table.remove("Ball"),
+        Some((ball.owner.clone(), ball.uses))
+    );
+    assert_eq!(table.get_by_secondary("Bob"), None);
+    assert_eq!(table.get_by_secondary2(&3), None);
+}